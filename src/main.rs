@@ -3,17 +3,37 @@ use std::{
     fs, io,
     io::Write,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use anyhow::{Context, Result, anyhow};
 use clap::{Parser, Subcommand};
 use dotenvy::dotenv;
-use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
-use reqwest::multipart;
-use serde::Deserialize;
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
 use slug::slugify;
+use tokio::sync::Semaphore;
 use zenity::spinner::MultiSpinner;
 
+mod storage;
+
+use storage::{BackendConfig, S3Config, StorageBackend, build_backend};
+
+/// Default number of files published to storage concurrently in `Publish`
+/// when given a directory.
+const DEFAULT_PUBLISH_CONCURRENCY: usize = 4;
+
+/// Max rows sent per PostgREST upsert request when batch-publishing.
+const METADATA_CHUNK_SIZE: usize = 50;
+
+/// Blurhash component counts (x, y) used for the placeholder string we
+/// store alongside each ingested image asset.
+const BLURHASH_COMPONENTS_X: u32 = 4;
+const BLURHASH_COMPONENTS_Y: u32 = 3;
+
+/// Longest `summary` frontmatter accepts before validation rejects the post.
+const MAX_SUMMARY_LEN: usize = 280;
+
 #[derive(Parser)]
 #[command(name = "supamarker")]
 #[command(about = "Publish markdown posts to Supabase (storage + posts table)")]
@@ -27,8 +47,13 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Publish a local markdown file
-    Publish { path: String },
+    /// Publish a local markdown file, or every .md file in a directory
+    Publish {
+        path: String,
+        /// When `path` is a directory, how many files to publish at once
+        #[arg(long, default_value_t = DEFAULT_PUBLISH_CONCURRENCY)]
+        concurrency: usize,
+    },
     /// Delete a post by slug
     Delete {
         slug: String,
@@ -38,39 +63,145 @@ enum Commands {
     },
     /// List slugs and where they exist
     List,
+    /// Reconcile drift between the storage bucket and the table
+    Sync {
+        /// Directory to search for local .md files when re-uploading rows
+        /// that are missing from storage (matched by `{slug}.md`)
+        #[arg(long)]
+        local_dir: Option<String>,
+        /// Print the planned actions without making any changes
+        #[arg(long)]
+        dry_run: bool,
+        /// Delete table rows that have no matching file in storage and no
+        /// local file to re-upload
+        #[arg(long)]
+        prune: bool,
+    },
     /// Generate a sample config at the default path
     GenConfig,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct FrontMatter {
     title: String,
     summary: Option<String>,
     tags: Option<Vec<String>>,
     slug: Option<String>,
+    /// Populated during publish by ingesting the post's local image links;
+    /// never read from the author's frontmatter.
+    #[serde(skip, default)]
+    assets: Vec<AssetMeta>,
 }
 
-async fn publish(
-    supabase_url: &str,
-    service_key: &str,
-    bucket: &str,
-    table: &str,
-    path: &str,
-) -> Result<()> {
-    let spinner = MultiSpinner::default();
-    let sid = spinner.get_last();
-    spinner.set_text(&sid, "Preparing file...".to_string());
+/// A local image asset uploaded alongside a post, with the blurhash of its
+/// downscaled pixels so front-ends can render a placeholder while the real
+/// image loads.
+#[derive(Debug, Clone, Serialize)]
+struct AssetMeta {
+    path: String,
+    blurhash: String,
+}
+
+/// As-parsed frontmatter, before validation. `title` is optional and `tags`
+/// entries are untyped so that a missing title or a non-string tag becomes
+/// an actionable validation issue instead of a terse serde error.
+#[derive(Debug, Deserialize)]
+struct RawFrontMatter {
+    title: Option<String>,
+    summary: Option<String>,
+    tags: Option<Vec<serde_yaml::Value>>,
+    slug: Option<String>,
+}
+
+/// Every problem found in a post's frontmatter, reported together so a
+/// malformed file doesn't have to be re-run once per fixed issue.
+#[derive(Debug)]
+struct FrontMatterError(Vec<String>);
+
+impl std::fmt::Display for FrontMatterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Invalid frontmatter:")?;
+        for issue in &self.0 {
+            writeln!(f, "  - {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for FrontMatterError {}
+
+/// Validate a post's raw frontmatter, collecting every problem instead of
+/// stopping at the first: an empty/whitespace title, tags that aren't plain
+/// strings, and a summary over `MAX_SUMMARY_LEN`. A `slug` that doesn't
+/// match its slugified form isn't an error — it's normalized, with a
+/// warning printed so the author knows what will actually be published.
+fn validate_frontmatter(raw: RawFrontMatter) -> Result<FrontMatter, FrontMatterError> {
+    let mut issues = Vec::new();
+
+    let title = match raw.title.as_deref().map(str::trim) {
+        Some(t) if !t.is_empty() => t.to_string(),
+        _ => {
+            issues.push("title: missing or empty".to_string());
+            String::new()
+        }
+    };
+
+    let tags = raw.tags.map(|values| {
+        let mut tags = Vec::with_capacity(values.len());
+        for value in values {
+            match value {
+                serde_yaml::Value::String(s) => tags.push(s),
+                other => issues.push(format!("tags: `{:?}` is not a plain string", other)),
+            }
+        }
+        tags
+    });
+
+    if let Some(summary) = &raw.summary {
+        let len = summary.chars().count();
+        if len > MAX_SUMMARY_LEN {
+            issues.push(format!(
+                "summary: {} characters, longer than the {} character limit",
+                len, MAX_SUMMARY_LEN
+            ));
+        }
+    }
+
+    let slug = raw.slug.map(|slug| {
+        let normalized = slugify(&slug);
+        if normalized != slug {
+            println!(
+                "⚠ slug `{}` is not a valid slug; using `{}` instead",
+                slug, normalized
+            );
+        }
+        normalized
+    });
+
+    if !issues.is_empty() {
+        return Err(FrontMatterError(issues));
+    }
 
-    // 1) Read file
+    Ok(FrontMatter {
+        title,
+        summary: raw.summary,
+        tags,
+        slug,
+        assets: Vec::new(),
+    })
+}
+
+/// Read a markdown file, parse its frontmatter, and resolve its slug
+/// (falling back to the file stem, then the title, when no `slug` field is
+/// set in frontmatter).
+fn prepare_post(path: &str) -> Result<(String, FrontMatter, String)> {
     let md = fs::read_to_string(path).with_context(|| format!("reading {}", path))?;
 
-    // 2) Extract frontmatter (simple YAML between --- markers)
-    //    We'll try to find `---\n...yaml...\n---\n` at start
+    // Extract frontmatter (simple YAML between --- markers)
     let (fm_opt, _) = parse_frontmatter(&md)?;
     let fm = fm_opt
         .ok_or_else(|| anyhow!("Frontmatter not found or invalid. Provide YAML frontmatter."))?;
 
-    // 3) Slug
     let slug = fm.slug.clone().unwrap_or_else(|| {
         Path::new(path)
             .file_stem()
@@ -79,88 +210,366 @@ async fn publish(
             .unwrap_or_else(|| slugify(&fm.title))
     });
 
-    // 4) Upload markdown file to Supabase Storage via REST API
-    // Endpoint: POST {SUPABASE_URL}/storage/v1/object/{bucket}/{object}
-    // (multipart/form-data field "file")
-    let upload_url = format!(
-        "{}/storage/v1/object/{}/{}.md",
-        supabase_url.trim_end_matches('/'),
-        bucket,
-        slug
-    );
+    Ok((slug, fm, md))
+}
 
-    let client = reqwest::Client::new();
+/// Ingest a post's local image links (upload each referenced asset, rewrite
+/// its link to the public storage URL), then upload the resulting markdown
+/// to storage. Shared by single-file and batch publishing so both paths get
+/// asset ingestion and the same upload behavior.
+async fn prepare_and_upload(backend: &dyn StorageBackend, path: &str) -> Result<(String, FrontMatter)> {
+    let (slug, mut fm, md) = prepare_post(path)?;
 
-    let file_name = format!("{}.md", slug);
-    let part = multipart::Part::text(md.clone())
-        .file_name(file_name)
-        .mime_str("text/markdown")?;
-    // note: we use "file" field like the JS SDK/multipart examples do
-    let form = multipart::Form::new().part("file", part);
+    let base_dir = Path::new(path)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
 
-    spinner.set_text(&sid, "Uploading markdown to storage...".to_string());
+    let (md, assets) = ingest_assets(backend, &slug, &base_dir, &md)
+        .await
+        .with_context(|| "ingesting local assets")?;
+    fm.assets = assets;
 
-    let upload_resp = client
-        .post(&upload_url)
-        .header(AUTHORIZATION, format!("Bearer {}", service_key))
-        // recommended Accept header
-        .header(ACCEPT, "application/json")
-        .multipart(form)
-        .send()
+    backend
+        .put(&format!("{}.md", slug), md.into_bytes(), "text/markdown")
         .await
-        .with_context(|| "uploading markdown to Supabase Storage")?;
+        .with_context(|| "uploading markdown to storage")?;
 
-    if !upload_resp.status().is_success() {
-        let status = upload_resp.status();
-        let text = upload_resp.text().await.unwrap_or_default();
-        return Err(anyhow!("Storage upload failed: {} - {}", status, text));
-    }
+    Ok((slug, fm))
+}
+
+async fn publish(
+    supabase_url: &str,
+    service_key: &str,
+    bucket: &str,
+    table: &str,
+    backend: &dyn StorageBackend,
+    path: &str,
+) -> Result<()> {
+    let spinner = MultiSpinner::default();
+    let sid = spinner.get_last();
+    spinner.set_text(&sid, "Preparing file...".to_string());
+
+    let client = reqwest::Client::new();
+
+    spinner.set_text(&sid, "Uploading markdown to storage...".to_string());
+
+    let (slug, fm) = prepare_and_upload(backend, path).await?;
 
     println!("✓ uploaded markdown to storage as {}/{}.md", bucket, slug);
 
     spinner.set_text(&sid, "Upserting metadata...".to_string());
 
-    // 5) Upsert metadata into your table via PostgREST (Supabase REST)
-    // Use the PostgREST endpoint: {SUPABASE_URL}/rest/v1/{SUPABASE_TABLE}
-    // We'll POST and set "Prefer: resolution=merge-duplicates" so conflict = upsert (merge)
+    upsert_metadata(&client, supabase_url, service_key, table, &slug, &fm)
+        .await
+        .with_context(|| format!("inserting/upserting metadata into {} table", table))?;
+
+    spinner.set_text(&sid, "Done.".to_string());
+    drop(spinner);
+
+    println!(
+        "✓ upserted metadata into {} table for slug `{}`",
+        table, slug
+    );
+    println!("Published ✅: {}", fm.title);
+
+    Ok(())
+}
+
+/// Publish every `.md` file in `dir` concurrently, bounded by a semaphore
+/// of `concurrency` permits. Storage uploads happen in parallel; once all
+/// uploads have settled, the metadata for the ones that succeeded is
+/// upserted in chunks of `METADATA_CHUNK_SIZE` rows rather than one
+/// PostgREST request per file.
+async fn publish_dir(
+    supabase_url: &str,
+    service_key: &str,
+    table: &str,
+    backend: Arc<dyn StorageBackend>,
+    dir: &str,
+    concurrency: usize,
+) -> Result<()> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("reading directory {}", dir))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("md"))
+        .collect();
+    paths.sort();
+
+    if paths.is_empty() {
+        println!("No .md files found in {}", dir);
+        return Ok(());
+    }
+
+    let client = reqwest::Client::new();
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+
+    let mut tasks = Vec::with_capacity(paths.len());
+    for path in paths {
+        let semaphore = Arc::clone(&semaphore);
+        let backend = Arc::clone(&backend);
+
+        tasks.push(tokio::spawn(async move {
+            let _permit = semaphore
+                .acquire_owned()
+                .await
+                .expect("publish semaphore was closed");
+            let label = path.display().to_string();
+
+            prepare_and_upload(backend.as_ref(), &label)
+                .await
+                .map_err(|e| (label, e.to_string()))
+        }));
+    }
+
+    let mut uploaded: Vec<(String, FrontMatter)> = Vec::new();
+    let mut failed: Vec<(String, String)> = Vec::new();
+    for task in tasks {
+        match task.await {
+            Ok(Ok(row)) => uploaded.push(row),
+            Ok(Err(err)) => failed.push(err),
+            Err(join_err) => failed.push(("<unknown>".to_string(), join_err.to_string())),
+        }
+    }
+
+    for chunk in uploaded.chunks(METADATA_CHUNK_SIZE) {
+        if let Err(e) = upsert_metadata_batch(&client, supabase_url, service_key, table, chunk).await
+        {
+            for (slug, _) in chunk {
+                failed.push((slug.clone(), format!("metadata upsert failed: {}", e)));
+            }
+        }
+    }
+    let failed_slugs: HashSet<&str> = failed.iter().map(|(slug, _)| slug.as_str()).collect();
+    let succeeded: Vec<&String> = uploaded
+        .iter()
+        .map(|(slug, _)| slug)
+        .filter(|slug| !failed_slugs.contains(slug.as_str()))
+        .collect();
+
+    println!("\nPublish summary: {} succeeded, {} failed", succeeded.len(), failed.len());
+    for slug in &succeeded {
+        println!("  ✓ {}", slug);
+    }
+    for (slug, err) in &failed {
+        println!("  ✗ {}: {}", slug, err);
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "{} of {} posts failed to publish",
+            failed.len(),
+            succeeded.len() + failed.len()
+        ))
+    }
+}
+
+/// Map a file extension to the MIME type passed to the storage backend's
+/// `put` for an ingested asset.
+fn mime_from_extension(ext: &str) -> &'static str {
+    match ext.to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Whether a markdown link target already points somewhere absolute, and so
+/// should be left untouched rather than treated as a local asset.
+fn is_absolute_link(link: &str) -> bool {
+    link.starts_with("http://")
+        || link.starts_with("https://")
+        || link.starts_with("//")
+        || link.starts_with("data:")
+}
+
+/// Extract the target of every markdown image/link (`![alt](target)` or
+/// `[text](target)`) in source order. A small hand-rolled scan, in keeping
+/// with `parse_frontmatter`'s approach, rather than pulling in a markdown
+/// parser for this one pattern.
+fn extract_markdown_links(md: &str) -> Vec<String> {
+    let mut links = Vec::new();
+    let bytes = md.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let is_image = bytes[i] == b'!' && bytes.get(i + 1) == Some(&b'[');
+        let bracket_start = if is_image { i + 1 } else { i };
+
+        if bytes[bracket_start] == b'[' {
+            if let Some(close_bracket) = md[bracket_start + 1..].find(']') {
+                let after_bracket = bracket_start + 1 + close_bracket + 1;
+                if md[after_bracket..].starts_with('(') {
+                    if let Some(close_paren) = md[after_bracket + 1..].find(')') {
+                        let link = &md[after_bracket + 1..after_bracket + 1 + close_paren];
+                        links.push(link.to_string());
+                        i = after_bracket + 1 + close_paren;
+                        continue;
+                    }
+                }
+            }
+        }
+
+        i += 1;
+    }
+
+    links
+}
+
+/// Downscale an image and encode it as a blurhash string so front-ends can
+/// show a placeholder while the real image loads.
+fn encode_blurhash(bytes: &[u8]) -> Result<String> {
+    let img = image::load_from_memory(bytes).context("decoding image for blurhash")?;
+    let small = img
+        .resize(32, 32, image::imageops::FilterType::Triangle)
+        .to_rgba8();
+    let (width, height) = small.dimensions();
+
+    blurhash::encode(
+        BLURHASH_COMPONENTS_X,
+        BLURHASH_COMPONENTS_Y,
+        width,
+        height,
+        &small.into_raw(),
+    )
+    .map_err(|e| anyhow!("blurhash encoding failed: {}", e))
+}
+
+/// Scan `md` for local image/file links, upload each referenced asset under
+/// `{slug}/assets/...` through `backend`, and rewrite the link to the asset's
+/// public storage URL. Links that are already absolute URLs are left alone.
+/// Returns the rewritten markdown plus the blurhash of every ingested asset
+/// that decodes as a raster image; assets that don't decode (e.g. SVG) get
+/// no entry.
+async fn ingest_assets(
+    backend: &dyn StorageBackend,
+    slug: &str,
+    base_dir: &Path,
+    md: &str,
+) -> Result<(String, Vec<AssetMeta>)> {
+    let mut rewritten = md.to_string();
+    let mut assets = Vec::new();
+
+    for link in extract_markdown_links(md) {
+        if is_absolute_link(&link) {
+            continue;
+        }
+
+        let local_path = base_dir.join(&link);
+        if !local_path.is_file() {
+            return Err(anyhow!(
+                "referenced asset `{}` not found at {}",
+                link,
+                local_path.display()
+            ));
+        }
+
+        let file_name = local_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow!("asset path `{}` has no file name", link))?
+            .to_string();
+        let ext = local_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+        let mime = mime_from_extension(ext);
+
+        let bytes = fs::read(&local_path)
+            .with_context(|| format!("reading asset {}", local_path.display()))?;
+
+        let storage_object = format!("{}/assets/{}", slug, file_name);
+        backend
+            .put(&storage_object, bytes.clone(), mime)
+            .await
+            .with_context(|| format!("uploading asset {}", file_name))?;
+
+        let public_url = backend.public_url(&storage_object);
+
+        rewritten = rewritten.replace(&format!("]({})", link), &format!("]({})", public_url));
+
+        if let Ok(blurhash) = encode_blurhash(&bytes) {
+            assets.push(AssetMeta {
+                path: storage_object,
+                blurhash,
+            });
+        }
+    }
+
+    Ok((rewritten, assets))
+}
+
+/// Download the markdown content stored as `{slug}.md`.
+async fn download_markdown(backend: &dyn StorageBackend, slug: &str) -> Result<String> {
+    let bytes = backend
+        .get(&format!("{}.md", slug))
+        .await
+        .with_context(|| format!("downloading storage object {}.md", slug))?;
+    String::from_utf8(bytes).context("storage object body was not valid UTF-8")
+}
+
+/// Upsert a single metadata row into the posts table via PostgREST, using
+/// `Prefer: resolution=merge-duplicates` so a conflict on `slug` overwrites
+/// the existing row.
+async fn upsert_metadata(
+    client: &reqwest::Client,
+    supabase_url: &str,
+    service_key: &str,
+    table: &str,
+    slug: &str,
+    fm: &FrontMatter,
+) -> Result<()> {
+    upsert_metadata_batch(client, supabase_url, service_key, table, &[(slug.to_string(), fm.clone())]).await
+}
+
+/// Upsert a chunk of metadata rows in a single PostgREST request, using
+/// `Prefer: resolution=merge-duplicates` so a conflict on `slug` overwrites
+/// the existing row. Used by `publish` (single row) and batch publishing
+/// (one request per `METADATA_CHUNK_SIZE` rows instead of one per file).
+async fn upsert_metadata_batch(
+    client: &reqwest::Client,
+    supabase_url: &str,
+    service_key: &str,
+    table: &str,
+    rows: &[(String, FrontMatter)],
+) -> Result<()> {
     let rest_url = format!("{}/rest/v1/{}", supabase_url.trim_end_matches('/'), table);
 
-    // Build JSON payload (we send an array with a single row)
-    let payload = serde_json::json!([{
-        "slug": slug,
-        "title": fm.title,
-        "summary": fm.summary.unwrap_or_default(),
-        "tags": fm.tags.unwrap_or_default()
-    }]);
+    let payload: Vec<_> = rows
+        .iter()
+        .map(|(slug, fm)| {
+            serde_json::json!({
+                "slug": slug,
+                "title": fm.title,
+                "summary": fm.summary.clone().unwrap_or_default(),
+                "tags": fm.tags.clone().unwrap_or_default(),
+                "assets": fm.assets.clone(),
+            })
+        })
+        .collect();
 
-    let metadata_resp = client
+    let resp = client
         .post(&rest_url)
         .header(AUTHORIZATION, format!("Bearer {}", service_key))
-        // required by Supabase PostgREST to identify project and allow the key
         .header("apikey", service_key)
-        // ask PostgREST to merge duplicates (upsert)
         .header("Prefer", "resolution=merge-duplicates")
         .header(CONTENT_TYPE, "application/json")
         .json(&payload)
         .send()
-        .await
-        .with_context(|| format!("inserting/upserting metadata into {} table", table))?;
+        .await?;
 
-    if !metadata_resp.status().is_success() {
-        let status = metadata_resp.status();
-        let text = metadata_resp.text().await.unwrap_or_default();
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
         return Err(anyhow!("DB upsert failed: {} - {}", status, text));
     }
 
-    spinner.set_text(&sid, "Done.".to_string());
-    drop(spinner);
-
-    println!(
-        "✓ upserted metadata into {} table for slug `{}`",
-        table, slug
-    );
-    println!("Published ✅: {}", fm.title);
-
     Ok(())
 }
 
@@ -168,6 +577,7 @@ async fn delete_post(
     supabase_url: &str,
     service_key: &str,
     bucket: &str,
+    backend: &dyn StorageBackend,
     slug: &str,
     table: &str,
     soft: bool,
@@ -179,9 +589,7 @@ async fn delete_post(
     let sid = spinner.get_last();
     spinner.set_text(&sid, format!("Verifying `{}`...", normalized_slug));
 
-    let storage_exists =
-        check_storage_presence(&client, supabase_url, service_key, bucket, &normalized_slug)
-            .await?;
+    let storage_exists = backend.exists(&format!("{}.md", normalized_slug)).await?;
     let table_exists =
         check_table_presence(&client, supabase_url, service_key, table, &normalized_slug).await?;
 
@@ -224,30 +632,9 @@ async fn delete_post(
             format!("Deleting markdown from storage: {}.md...", normalized_slug),
         );
 
-        let storage_url = format!(
-            "{}/storage/v1/object/{}/{}.md",
-            supabase_url.trim_end_matches('/'),
-            bucket,
-            normalized_slug
-        );
-
-        let storage_resp = client
-            .delete(&storage_url)
-            .header(AUTHORIZATION, format!("Bearer {}", service_key))
-            .header("apikey", service_key) // needed for service role
-            .header("Accept", "application/json")
-            .send()
-            .await?;
-
-        if !storage_resp.status().is_success() {
-            let status = storage_resp.status();
-            let text = storage_resp.text().await.unwrap_or_default();
+        if let Err(e) = backend.delete(&format!("{}.md", normalized_slug)).await {
             drop(spinner);
-            return Err(anyhow!(
-                "Failed to delete storage file: {} - {}",
-                status,
-                text
-            ));
+            return Err(e.context("failed to delete storage file"));
         }
 
         spinner.set_text(&sid, "Deleted from storage.".to_string());
@@ -323,7 +710,8 @@ fn parse_frontmatter(s: &str) -> Result<(Option<FrontMatter>, String)> {
     let yaml = yaml.trim();
     let rest = rest.trim_start_matches('\n').to_string();
 
-    let fm: FrontMatter = serde_yaml::from_str(yaml).context("parsing YAML frontmatter")?;
+    let raw: RawFrontMatter = serde_yaml::from_str(yaml).context("parsing YAML frontmatter")?;
+    let fm = validate_frontmatter(raw)?;
     Ok((Some(fm), rest))
 }
 
@@ -333,6 +721,13 @@ struct FileConfig {
     supabase_service_key: Option<String>,
     bucket: Option<String>,
     table: Option<String>,
+    /// Which `StorageBackend` to use: "supabase" (default), "s3", or "local".
+    backend: Option<String>,
+    s3_endpoint: Option<String>,
+    s3_region: Option<String>,
+    s3_access_key: Option<String>,
+    s3_secret_key: Option<String>,
+    local_root: Option<String>,
 }
 
 struct ResolvedConfig {
@@ -340,6 +735,22 @@ struct ResolvedConfig {
     service_key: String,
     bucket: String,
     table: String,
+    backend: String,
+    s3: Option<S3Config>,
+    local_root: Option<PathBuf>,
+}
+
+impl ResolvedConfig {
+    fn backend(&self) -> Result<Box<dyn StorageBackend>> {
+        build_backend(&BackendConfig {
+            kind: self.backend.clone(),
+            supabase_url: self.supabase_url.clone(),
+            service_key: self.service_key.clone(),
+            bucket: self.bucket.clone(),
+            s3: self.s3.clone(),
+            local_root: self.local_root.clone(),
+        })
+    }
 }
 
 fn candidate_config_paths(cli_path: Option<&str>) -> Vec<PathBuf> {
@@ -422,11 +833,50 @@ fn load_config(cli_config: Option<&str>) -> Result<ResolvedConfig> {
         .or_else(|| std::env::var("SUPABASE_TABLE").ok())
         .unwrap_or_else(|| "posts".to_string());
 
+    let backend = file_cfg
+        .as_ref()
+        .and_then(|c| c.backend.clone())
+        .or_else(|| std::env::var("SUPAMARKER_BACKEND").ok())
+        .unwrap_or_else(|| "supabase".to_string());
+
+    let s3 = match (
+        file_cfg.as_ref().and_then(|c| c.s3_region.clone()),
+        file_cfg.as_ref().and_then(|c| c.s3_access_key.clone()),
+        file_cfg.as_ref().and_then(|c| c.s3_secret_key.clone()),
+    ) {
+        (Some(region), Some(access_key), Some(secret_key)) => {
+            // `s3_endpoint` is only needed for non-Supabase S3-compatible
+            // stores; against Supabase itself it's this well-known path.
+            let endpoint = file_cfg
+                .as_ref()
+                .and_then(|c| c.s3_endpoint.clone())
+                .unwrap_or_else(|| {
+                    format!("{}/storage/v1/s3", supabase_url.trim_end_matches('/'))
+                });
+            Some(S3Config {
+                endpoint,
+                region,
+                access_key,
+                secret_key,
+                bucket: bucket.clone(),
+            })
+        }
+        _ => None,
+    };
+
+    let local_root = file_cfg
+        .as_ref()
+        .and_then(|c| c.local_root.clone())
+        .map(PathBuf::from);
+
     Ok(ResolvedConfig {
         supabase_url,
         service_key,
         bucket,
         table,
+        backend,
+        s3,
+        local_root,
     })
 }
 
@@ -436,23 +886,40 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Cli::parse();
 
     match args.cmd {
-        Commands::Publish { path } => {
+        Commands::Publish { path, concurrency } => {
             let config = load_config(args.config.as_deref())?;
-            publish(
-                &config.supabase_url,
-                &config.service_key,
-                &config.bucket,
-                &config.table,
-                &path,
-            )
-            .await?;
+            if Path::new(&path).is_dir() {
+                let backend: Arc<dyn StorageBackend> = Arc::from(config.backend()?);
+                publish_dir(
+                    &config.supabase_url,
+                    &config.service_key,
+                    &config.table,
+                    backend,
+                    &path,
+                    concurrency,
+                )
+                .await?;
+            } else {
+                let backend = config.backend()?;
+                publish(
+                    &config.supabase_url,
+                    &config.service_key,
+                    &config.bucket,
+                    &config.table,
+                    backend.as_ref(),
+                    &path,
+                )
+                .await?;
+            }
         }
         Commands::Delete { slug, soft } => {
             let config = load_config(args.config.as_deref())?;
+            let backend = config.backend()?;
             delete_post(
                 &config.supabase_url,
                 &config.service_key,
                 &config.bucket,
+                backend.as_ref(),
                 &slug,
                 &config.table,
                 soft,
@@ -461,11 +928,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         Commands::List => {
             let config = load_config(args.config.as_deref())?;
+            let backend = config.backend()?;
             list_items(
+                &config.supabase_url,
+                &config.service_key,
+                &config.table,
+                backend.as_ref(),
+            )
+            .await?;
+        }
+        Commands::Sync {
+            local_dir,
+            dry_run,
+            prune,
+        } => {
+            let config = load_config(args.config.as_deref())?;
+            let backend = config.backend()?;
+            sync_items(
                 &config.supabase_url,
                 &config.service_key,
                 &config.bucket,
                 &config.table,
+                backend.as_ref(),
+                local_dir.as_deref(),
+                dry_run,
+                prune,
             )
             .await?;
         }
@@ -545,6 +1032,21 @@ fn gen_config() -> Result<PathBuf> {
 supabase_service_key = "service_role_key"
 bucket = "blog"
 table = "posts"
+
+# Which StorageBackend handles object storage: "supabase" (default), "s3", or "local".
+# backend = "supabase"
+
+# Required when backend = "s3": signs requests with AWS SigV4 using an
+# access-key/secret pair instead of the service-role key above. s3_endpoint
+# defaults to Supabase's own S3-compatible endpoint if omitted; set it
+# explicitly to point at a different S3-compatible store.
+# s3_region = "us-east-1"
+# s3_access_key = "..."
+# s3_secret_key = "..."
+# s3_endpoint = "https://xxxxx.supabase.co/storage/v1/s3"
+
+# Used when backend = "local"; defaults to ./supamarker-storage.
+# local_root = "./storage"
 "#;
 
     fs::write(&path, sample).with_context(|| format!("writing config to {}", path.display()))?;
@@ -565,30 +1067,6 @@ fn prompt_confirm(question: &str) -> Result<bool> {
     Ok(resp == "y" || resp == "yes")
 }
 
-async fn check_storage_presence(
-    client: &reqwest::Client,
-    supabase_url: &str,
-    service_key: &str,
-    bucket: &str,
-    slug: &str,
-) -> Result<bool> {
-    let url = format!(
-        "{}/storage/v1/object/{}/{}.md",
-        supabase_url.trim_end_matches('/'),
-        bucket,
-        slug
-    );
-
-    let resp = client
-        .head(url)
-        .header(AUTHORIZATION, format!("Bearer {}", service_key))
-        .header("apikey", service_key)
-        .send()
-        .await?;
-
-    Ok(resp.status().is_success())
-}
-
 #[derive(Deserialize)]
 struct TableRow {
     slug: String,
@@ -630,45 +1108,18 @@ async fn check_table_presence(
     Ok(!rows.is_empty())
 }
 
-#[derive(Deserialize)]
-struct StorageObject {
-    name: String,
-}
-
-async fn fetch_storage_slugs(
-    client: &reqwest::Client,
-    supabase_url: &str,
-    service_key: &str,
-    bucket: &str,
-) -> Result<Vec<String>> {
-    let url = format!(
-        "{}/storage/v1/object/list/{}",
-        supabase_url.trim_end_matches('/'),
-        bucket
-    );
-
-    let resp = client
-        .post(url)
-        .header(AUTHORIZATION, format!("Bearer {}", service_key))
-        .header("apikey", service_key)
-        .header("Accept", "application/json")
-        .header(CONTENT_TYPE, "application/json")
-        .json(&serde_json::json!({ "prefix": "" }))
-        .send()
-        .await?;
-
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        return Err(anyhow!(
-            "Failed to list storage objects: {} - {}",
-            status,
-            text
-        ));
-    }
-
-    let files: Vec<StorageObject> = resp.json().await?;
-    Ok(files.into_iter().map(|f| normalize_slug(&f.name)).collect())
+/// List every slug currently in storage, derived from the object names
+/// under `{slug}.md` at the bucket root.
+async fn fetch_storage_slugs(backend: &dyn StorageBackend) -> Result<Vec<String>> {
+    let names = backend
+        .list("")
+        .await
+        .context("listing storage objects")?;
+    Ok(names
+        .into_iter()
+        .filter(|name| name.ends_with(".md") && !name.contains('/'))
+        .map(|name| normalize_slug(&name))
+        .collect())
 }
 
 async fn fetch_table_slugs(
@@ -704,15 +1155,15 @@ async fn fetch_table_slugs(
 async fn list_items(
     supabase_url: &str,
     service_key: &str,
-    bucket: &str,
     table: &str,
+    backend: &dyn StorageBackend,
 ) -> Result<()> {
     let client = reqwest::Client::new();
     let spinner = MultiSpinner::default();
     let sid = spinner.get_last();
     spinner.set_text(&sid, "Fetching storage objects...".to_string());
 
-    let storage_slugs = fetch_storage_slugs(&client, supabase_url, service_key, bucket).await?;
+    let storage_slugs = fetch_storage_slugs(backend).await?;
     spinner.set_text(&sid, "Fetching table rows...".to_string());
 
     let table_slugs = fetch_table_slugs(&client, supabase_url, service_key, table).await?;
@@ -745,3 +1196,153 @@ async fn list_items(
 
     Ok(())
 }
+
+/// Reconcile drift between the storage bucket and the posts table.
+///
+/// - Slugs in storage but missing from the table: download the `.md`,
+///   re-parse its frontmatter, and upsert the metadata row.
+/// - Slugs in the table but missing from storage: re-upload from
+///   `local_dir/{slug}.md` if present, otherwise flag as orphaned (and
+///   delete the row if `prune` is set).
+#[allow(clippy::too_many_arguments)]
+async fn sync_items(
+    supabase_url: &str,
+    service_key: &str,
+    bucket: &str,
+    table: &str,
+    backend: &dyn StorageBackend,
+    local_dir: Option<&str>,
+    dry_run: bool,
+    prune: bool,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let spinner = MultiSpinner::default();
+    let sid = spinner.get_last();
+    spinner.set_text(&sid, "Fetching storage objects...".to_string());
+
+    let storage_slugs = fetch_storage_slugs(backend).await?;
+    spinner.set_text(&sid, "Fetching table rows...".to_string());
+
+    let table_slugs = fetch_table_slugs(&client, supabase_url, service_key, table).await?;
+    spinner.set_text(&sid, "Computing differences...".to_string());
+    drop(spinner);
+
+    let storage_set: HashSet<String> = storage_slugs.into_iter().collect();
+    let table_set: HashSet<String> = table_slugs.into_iter().collect();
+
+    let mut storage_only: Vec<&String> = storage_set.difference(&table_set).collect();
+    storage_only.sort();
+    let mut table_only: Vec<&String> = table_set.difference(&storage_set).collect();
+    table_only.sort();
+
+    if storage_only.is_empty() && table_only.is_empty() {
+        println!("Storage and table are already in sync.");
+        return Ok(());
+    }
+
+    // Slugs in storage only: backfill the table from the stored markdown.
+    // A single bad file (failed download, invalid frontmatter) shouldn't
+    // abort the rest of the reconcile, so each slug's failure is isolated
+    // and reported rather than propagated with `?`.
+    let mut failed: Vec<(String, String)> = Vec::new();
+    for slug in &storage_only {
+        if dry_run {
+            println!("[dry-run] would upsert `{}` from storage into `{}`", slug, table);
+            continue;
+        }
+
+        let result: Result<()> = async {
+            let md = download_markdown(backend, slug).await?;
+            let (fm_opt, _) = parse_frontmatter(&md)?;
+            let fm = fm_opt.ok_or_else(|| {
+                anyhow!(
+                    "`{}` is in storage but has no valid frontmatter; cannot backfill table row",
+                    slug
+                )
+            })?;
+
+            upsert_metadata(&client, supabase_url, service_key, table, slug, &fm)
+                .await
+                .with_context(|| format!("upserting metadata for `{}`", slug))?;
+
+            Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => println!("✓ backfilled `{}` into {} table", slug, table),
+            Err(e) => {
+                println!("✗ failed to backfill `{}`: {}", slug, e);
+                failed.push((slug.to_string(), e.to_string()));
+            }
+        }
+    }
+
+    // Slugs in the table only: re-upload if a local file is supplied,
+    // otherwise they're orphaned rows.
+    for slug in &table_only {
+        let local_path = local_dir.map(|dir| Path::new(dir).join(format!("{}.md", slug)));
+        let local_md = local_path
+            .as_ref()
+            .filter(|p| p.exists())
+            .map(|p| fs::read_to_string(p).with_context(|| format!("reading {}", p.display())))
+            .transpose()?;
+
+        if let Some(md) = local_md {
+            if dry_run {
+                println!("[dry-run] would re-upload `{}` from {}", slug, local_path.unwrap().display());
+                continue;
+            }
+
+            backend
+                .put(&format!("{}.md", slug), md.into_bytes(), "text/markdown")
+                .await
+                .with_context(|| format!("re-uploading `{}`", slug))?;
+
+            println!("✓ re-uploaded `{}` to {} bucket", slug, bucket);
+        } else if prune {
+            if dry_run {
+                println!("[dry-run] would delete orphaned row `{}` from `{}`", slug, table);
+                continue;
+            }
+
+            let rest_url = format!(
+                "{}/rest/v1/{}?slug=eq.{}",
+                supabase_url.trim_end_matches('/'),
+                table,
+                slug
+            );
+            let resp = client
+                .delete(&rest_url)
+                .header(AUTHORIZATION, format!("Bearer {}", service_key))
+                .header("apikey", service_key)
+                .header("Accept", "application/json")
+                .send()
+                .await?;
+
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let text = resp.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "Failed to delete orphaned row `{}`: {} - {}",
+                    slug,
+                    status,
+                    text
+                ));
+            }
+
+            println!("✓ pruned orphaned row `{}` from {} table", slug, table);
+        } else {
+            println!(
+                "⚠ `{}` is orphaned: present in `{}` but missing from storage (pass --local-dir to re-upload or --prune to delete)",
+                slug, table
+            );
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("{} slug(s) failed to sync from storage", failed.len()))
+    }
+}
@@ -0,0 +1,88 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use super::StorageBackend;
+
+/// Stores objects as plain files under `root`. Useful for tests and for
+/// previewing a publish without touching a live project.
+pub struct LocalBackend {
+    root: PathBuf,
+}
+
+impl LocalBackend {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn resolve(&self, object_path: &str) -> PathBuf {
+        self.root.join(object_path)
+    }
+}
+
+#[async_trait]
+impl StorageBackend for LocalBackend {
+    async fn put(&self, object_path: &str, bytes: Vec<u8>, _mime: &str) -> Result<()> {
+        let path = self.resolve(object_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("creating directory {}", parent.display()))?;
+        }
+        fs::write(&path, bytes).with_context(|| format!("writing {}", path.display()))
+    }
+
+    async fn get(&self, object_path: &str) -> Result<Vec<u8>> {
+        let path = self.resolve(object_path);
+        fs::read(&path).with_context(|| format!("reading {}", path.display()))
+    }
+
+    async fn delete(&self, object_path: &str) -> Result<()> {
+        let path = self.resolve(object_path);
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("deleting {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, object_path: &str) -> Result<bool> {
+        Ok(self.resolve(object_path).is_file())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let base = self.resolve(prefix);
+        let mut names = Vec::new();
+
+        if base.is_dir() {
+            collect_files(&self.root, &base, &mut names)?;
+        } else if base.is_file() {
+            if let Ok(rel) = base.strip_prefix(&self.root) {
+                names.push(to_object_path(rel));
+            }
+        }
+
+        names.sort();
+        Ok(names)
+    }
+
+    fn public_url(&self, object_path: &str) -> String {
+        format!("file://{}", self.resolve(object_path).display())
+    }
+}
+
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if let Ok(rel) = path.strip_prefix(root) {
+            out.push(to_object_path(rel));
+        }
+    }
+    Ok(())
+}
+
+fn to_object_path(rel: &Path) -> String {
+    rel.to_string_lossy().replace('\\', "/")
+}
@@ -0,0 +1,73 @@
+mod local;
+mod s3;
+mod supabase;
+
+use std::path::PathBuf;
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+
+pub use local::LocalBackend;
+pub use s3::{S3Backend, S3Config};
+pub use supabase::SupabaseBackend;
+
+/// Page size `SupabaseBackend` requests per object-listing call. The S3
+/// backend pages with continuation tokens and does not use it.
+const LIST_PAGE_SIZE: usize = 100;
+
+/// Storage operations `publish`, `delete_post`, `list_items`, and `sync`
+/// need, independent of where the bytes actually live. Selected at runtime
+/// from config so the rest of the tool stays backend-agnostic.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// Upload `bytes` as `object_path`, replacing any existing object.
+    async fn put(&self, object_path: &str, bytes: Vec<u8>, mime: &str) -> Result<()>;
+    /// Download the bytes stored at `object_path`.
+    async fn get(&self, object_path: &str) -> Result<Vec<u8>>;
+    /// Remove `object_path`, if present.
+    async fn delete(&self, object_path: &str) -> Result<()>;
+    /// Whether `object_path` currently exists.
+    async fn exists(&self, object_path: &str) -> Result<bool>;
+    /// List every object path under `prefix`.
+    async fn list(&self, prefix: &str) -> Result<Vec<String>>;
+    /// A URL a reader's browser can fetch `object_path` from.
+    fn public_url(&self, object_path: &str) -> String;
+}
+
+/// Everything needed to construct whichever backend `kind` selects.
+pub struct BackendConfig {
+    pub kind: String,
+    pub supabase_url: String,
+    pub service_key: String,
+    pub bucket: String,
+    pub s3: Option<S3Config>,
+    pub local_root: Option<PathBuf>,
+}
+
+pub fn build_backend(config: &BackendConfig) -> Result<Box<dyn StorageBackend>> {
+    match config.kind.as_str() {
+        "supabase" => Ok(Box::new(SupabaseBackend::new(
+            config.supabase_url.clone(),
+            config.service_key.clone(),
+            config.bucket.clone(),
+        ))),
+        "s3" => {
+            let s3 = config
+                .s3
+                .clone()
+                .ok_or_else(|| anyhow!("backend = \"s3\" requires s3_region/s3_access_key/s3_secret_key in config"))?;
+            Ok(Box::new(S3Backend::new(s3)))
+        }
+        "local" => {
+            let root = config
+                .local_root
+                .clone()
+                .unwrap_or_else(|| PathBuf::from("./supamarker-storage"));
+            Ok(Box::new(LocalBackend::new(root)))
+        }
+        other => Err(anyhow!(
+            "Unknown backend `{}`; expected \"supabase\", \"s3\", or \"local\"",
+            other
+        )),
+    }
+}
@@ -0,0 +1,360 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::Method;
+use reqwest::header::CONTENT_TYPE;
+use sha2::{Digest, Sha256};
+
+use super::StorageBackend;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Static credentials and location for an S3-compatible bucket.
+#[derive(Debug, Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub bucket: String,
+}
+
+/// Talks to any S3-compatible object store (AWS S3, MinIO, Backblaze B2,
+/// Supabase's S3-compatible endpoint, ...) over path-style requests,
+/// authenticated with AWS Signature Version 4.
+pub struct S3Backend {
+    client: reqwest::Client,
+    config: S3Config,
+}
+
+impl S3Backend {
+    pub fn new(config: S3Config) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+        }
+    }
+
+    /// `object_path` may be empty for bucket-level requests (e.g. listing).
+    fn object_url(&self, object_path: &str) -> String {
+        let base = format!(
+            "{}/{}",
+            self.config.endpoint.trim_end_matches('/'),
+            self.config.bucket
+        );
+        if object_path.is_empty() {
+            base
+        } else {
+            format!("{}/{}", base, object_path)
+        }
+    }
+
+    fn host(&self) -> Result<String> {
+        let url = reqwest::Url::parse(&self.config.endpoint)
+            .map_err(|e| anyhow!("invalid s3 endpoint `{}`: {}", self.config.endpoint, e))?;
+        url.host_str()
+            .map(|h| match url.port() {
+                Some(port) => format!("{}:{}", h, port),
+                None => h.to_string(),
+            })
+            .ok_or_else(|| anyhow!("s3 endpoint `{}` has no host", self.config.endpoint))
+    }
+
+    async fn request(&self, method: Method, object_path: &str, body: Vec<u8>) -> Result<reqwest::Response> {
+        self.request_with_query(method, object_path, &[], body).await
+    }
+
+    /// Like `request`, but also signs and sends `query` (e.g. `ListObjectsV2`'s
+    /// `list-type`/`prefix`/`continuation-token`). `query` is sent exactly as
+    /// built here, so the signed canonical query string always matches what's
+    /// actually on the wire.
+    async fn request_with_query(
+        &self,
+        method: Method,
+        object_path: &str,
+        query: &[(&str, String)],
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response> {
+        self.request_with_content_type(method, object_path, query, None, body).await
+    }
+
+    /// Like `request_with_query`, but also sets `Content-Type` when `mime`
+    /// is given (used by `put` so uploaded objects carry the right type;
+    /// this header isn't in `signed_headers`, so it doesn't affect signing).
+    async fn request_with_content_type(
+        &self,
+        method: Method,
+        object_path: &str,
+        query: &[(&str, String)],
+        mime: Option<&str>,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response> {
+        let base_url = self.object_url(object_path);
+        // SigV4 requires the canonical URI to match the request's actual
+        // absolute path, which includes whatever path `endpoint` already
+        // has (e.g. Supabase's `/storage/v1/s3`), not just `/bucket/object`.
+        let canonical_path = reqwest::Url::parse(&base_url)
+            .map_err(|e| anyhow!("invalid s3 endpoint `{}`: {}", self.config.endpoint, e))?
+            .path()
+            .to_string();
+        let canonical_query = canonical_query_string(query);
+        let url = if canonical_query.is_empty() {
+            base_url
+        } else {
+            format!("{}?{}", base_url, canonical_query)
+        };
+
+        let host = self.host()?;
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date_stamp = now.format("%Y%m%d").to_string();
+        let payload_hash = sha256_hex(&body);
+
+        let (authorization, signed_headers) = sign_request(
+            &self.config,
+            method.as_str(),
+            &canonical_path,
+            &canonical_query,
+            &host,
+            &amz_date,
+            &date_stamp,
+            &payload_hash,
+        );
+        let _ = signed_headers;
+
+        let mut req = self
+            .client
+            .request(method, url)
+            .header("host", host)
+            .header("x-amz-date", &amz_date)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("authorization", authorization);
+
+        if let Some(mime) = mime {
+            req = req.header(CONTENT_TYPE, mime);
+        }
+
+        if !body.is_empty() {
+            req = req.body(body);
+        }
+
+        Ok(req.send().await?)
+    }
+}
+
+/// URI-encode a query key or value per AWS's SigV4 rules (RFC 3986
+/// unreserved characters pass through; everything else becomes `%XX`).
+fn uri_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// Build the canonical query string AWS's SigV4 docs require: keys and
+/// values URI-encoded, then pairs sorted by (encoded) key.
+fn canonical_query_string(query: &[(&str, String)]) -> String {
+    let mut pairs: Vec<(String, String)> = query
+        .iter()
+        .map(|(k, v)| (uri_encode(k), uri_encode(v)))
+        .collect();
+    pairs.sort();
+    pairs
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Build the canonical request as described in AWS's SigV4 docs:
+/// `METHOD\nURI\ncanonical-query\ncanonical-headers\nsigned-headers\nSHA256(payload-hex)`,
+/// then sign it, returning the `Authorization` header value and the
+/// `signed_headers` string used to build it.
+#[allow(clippy::too_many_arguments)]
+fn sign_request(
+    config: &S3Config,
+    method: &str,
+    canonical_path: &str,
+    canonical_query: &str,
+    host: &str,
+    amz_date: &str,
+    date_stamp: &str,
+    payload_hash: &str,
+) -> (String, String) {
+    let canonical_headers = format!(
+        "host:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n",
+        host, payload_hash, amz_date
+    );
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method, canonical_path, canonical_query, canonical_headers, signed_headers, payload_hash
+    );
+
+    let scope = format!("{}/{}/s3/aws4_request", date_stamp, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        sha256_hex(canonical_request.as_bytes())
+    );
+
+    let k_date = hmac_sha256(format!("AWS4{}", config.secret_key).as_bytes(), date_stamp.as_bytes());
+    let k_region = hmac_sha256(&k_date, config.region.as_bytes());
+    let k_service = hmac_sha256(&k_region, b"s3");
+    let signing_key = hmac_sha256(&k_service, b"aws4_request");
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        config.access_key, scope, signed_headers, signature
+    );
+
+    (authorization, signed_headers.to_string())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hex::encode(hasher.finalize())
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Extract the text content of every `<tag>...</tag>` element in an XML
+/// response body, at any nesting depth (e.g. each `<Key>` inside its
+/// `<Contents>`). `ListObjectsV2` responses are flat enough that a
+/// small hand-rolled scan does the job without pulling in an XML parser.
+fn extract_xml_tag(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut values = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find(&open) {
+        let after_open = &rest[start + open.len()..];
+        match after_open.find(&close) {
+            Some(end) => {
+                values.push(after_open[..end].to_string());
+                rest = &after_open[end + close.len()..];
+            }
+            None => break,
+        }
+    }
+
+    values
+}
+
+#[async_trait]
+impl StorageBackend for S3Backend {
+    async fn put(&self, object_path: &str, bytes: Vec<u8>, mime: &str) -> Result<()> {
+        let resp = self
+            .request_with_content_type(Method::PUT, object_path, &[], Some(mime), bytes)
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("S3 upload failed: {} - {}", status, text));
+        }
+        Ok(())
+    }
+
+    async fn get(&self, object_path: &str) -> Result<Vec<u8>> {
+        let resp = self.request(Method::GET, object_path, Vec::new()).await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Failed to download {}: {} - {}",
+                object_path,
+                status,
+                text
+            ));
+        }
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    async fn delete(&self, object_path: &str) -> Result<()> {
+        let resp = self.request(Method::DELETE, object_path, Vec::new()).await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Failed to delete {}: {} - {}",
+                object_path,
+                status,
+                text
+            ));
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, object_path: &str) -> Result<bool> {
+        let resp = self.request(Method::HEAD, object_path, Vec::new()).await?;
+        Ok(resp.status().is_success())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let mut query: Vec<(&str, String)> = vec![
+                ("list-type", "2".to_string()),
+                ("prefix", prefix.to_string()),
+            ];
+            if let Some(token) = &continuation_token {
+                query.push(("continuation-token", token.clone()));
+            }
+
+            let resp = self
+                .request_with_query(Method::GET, "", &query, Vec::new())
+                .await?;
+
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let text = resp.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "Failed to list objects (prefix `{}`): {} - {}",
+                    prefix,
+                    status,
+                    text
+                ));
+            }
+
+            let body = resp.text().await?;
+            names.extend(extract_xml_tag(&body, "Key"));
+
+            let truncated = extract_xml_tag(&body, "IsTruncated")
+                .first()
+                .map(|v| v == "true")
+                .unwrap_or(false);
+            if !truncated {
+                break;
+            }
+
+            continuation_token = extract_xml_tag(&body, "NextContinuationToken").into_iter().next();
+            if continuation_token.is_none() {
+                break;
+            }
+        }
+
+        Ok(names)
+    }
+
+    fn public_url(&self, object_path: &str) -> String {
+        self.object_url(object_path)
+    }
+}
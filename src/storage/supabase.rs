@@ -0,0 +1,189 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::multipart;
+use serde::Deserialize;
+
+use super::{LIST_PAGE_SIZE, StorageBackend};
+
+/// Talks to Supabase Storage's REST API with a service-role Bearer token.
+pub struct SupabaseBackend {
+    client: reqwest::Client,
+    supabase_url: String,
+    service_key: String,
+    bucket: String,
+}
+
+impl SupabaseBackend {
+    pub fn new(supabase_url: String, service_key: String, bucket: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            supabase_url,
+            service_key,
+            bucket,
+        }
+    }
+
+    fn object_url(&self, object_path: &str) -> String {
+        format!(
+            "{}/storage/v1/object/{}/{}",
+            self.supabase_url.trim_end_matches('/'),
+            self.bucket,
+            object_path
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct ListEntry {
+    name: String,
+}
+
+#[async_trait]
+impl StorageBackend for SupabaseBackend {
+    async fn put(&self, object_path: &str, bytes: Vec<u8>, mime: &str) -> Result<()> {
+        let file_name = object_path
+            .rsplit('/')
+            .next()
+            .unwrap_or(object_path)
+            .to_string();
+        let part = multipart::Part::bytes(bytes)
+            .file_name(file_name)
+            .mime_str(mime)?;
+        let form = multipart::Form::new().part("file", part);
+
+        let resp = self
+            .client
+            .post(self.object_url(object_path))
+            .header(AUTHORIZATION, format!("Bearer {}", self.service_key))
+            .header(ACCEPT, "application/json")
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("Storage upload failed: {} - {}", status, text));
+        }
+
+        Ok(())
+    }
+
+    async fn get(&self, object_path: &str) -> Result<Vec<u8>> {
+        let resp = self
+            .client
+            .get(self.object_url(object_path))
+            .header(AUTHORIZATION, format!("Bearer {}", self.service_key))
+            .header("apikey", &self.service_key)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Failed to download {}: {} - {}",
+                object_path,
+                status,
+                text
+            ));
+        }
+
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    async fn delete(&self, object_path: &str) -> Result<()> {
+        let resp = self
+            .client
+            .delete(self.object_url(object_path))
+            .header(AUTHORIZATION, format!("Bearer {}", self.service_key))
+            .header("apikey", &self.service_key)
+            .header(ACCEPT, "application/json")
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Failed to delete {}: {} - {}",
+                object_path,
+                status,
+                text
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn exists(&self, object_path: &str) -> Result<bool> {
+        let resp = self
+            .client
+            .head(self.object_url(object_path))
+            .header(AUTHORIZATION, format!("Bearer {}", self.service_key))
+            .header("apikey", &self.service_key)
+            .send()
+            .await?;
+
+        Ok(resp.status().is_success())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let url = format!(
+            "{}/storage/v1/object/list/{}",
+            self.supabase_url.trim_end_matches('/'),
+            self.bucket
+        );
+
+        let mut names = Vec::new();
+        let mut offset = 0usize;
+
+        loop {
+            let resp = self
+                .client
+                .post(&url)
+                .header(AUTHORIZATION, format!("Bearer {}", self.service_key))
+                .header("apikey", &self.service_key)
+                .header(ACCEPT, "application/json")
+                .header(CONTENT_TYPE, "application/json")
+                .json(&serde_json::json!({
+                    "prefix": prefix,
+                    "limit": LIST_PAGE_SIZE,
+                    "offset": offset,
+                }))
+                .send()
+                .await?;
+
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let text = resp.text().await.unwrap_or_default();
+                return Err(anyhow!(
+                    "Failed to list storage objects: {} - {}",
+                    status,
+                    text
+                ));
+            }
+
+            let page: Vec<ListEntry> = resp.json().await?;
+            let page_len = page.len();
+            names.extend(page.into_iter().map(|e| e.name));
+
+            if page_len < LIST_PAGE_SIZE {
+                break;
+            }
+            offset += page_len;
+        }
+
+        Ok(names)
+    }
+
+    fn public_url(&self, object_path: &str) -> String {
+        format!(
+            "{}/storage/v1/object/public/{}/{}",
+            self.supabase_url.trim_end_matches('/'),
+            self.bucket,
+            object_path
+        )
+    }
+}